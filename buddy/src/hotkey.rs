@@ -1,33 +1,33 @@
-use crate::config::HotkeyConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-#[cfg(target_os = "windows")]
-use std::sync::Arc;
-
-#[cfg(target_os = "windows")]
 use tokio::sync::mpsc::{self, UnboundedReceiver};
 
-#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
 #[derive(Debug)]
 pub enum HotkeyError {
     Parse(String),
-    #[cfg(target_os = "windows")]
     Manager(global_hotkey::GlobalHotKeyError),
-    #[cfg(target_os = "windows")]
     Register(global_hotkey::GlobalHotKeyError),
+    HotkeyAlreadyRegistered(String),
+    HotkeyNotRegistered(String),
     Channel,
-    Interrupt(std::io::Error),
+    Config(crate::keymap::ParseError),
 }
 
 impl std::fmt::Display for HotkeyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Parse(key) => write!(f, "invalid hotkey '{}'", key),
-            #[cfg(target_os = "windows")]
             Self::Manager(err) => write!(f, "global hotkey manager error: {}", err),
-            #[cfg(target_os = "windows")]
             Self::Register(err) => write!(f, "failed to register hotkey: {}", err),
+            Self::HotkeyAlreadyRegistered(name) => {
+                write!(f, "'{}' is already bound to a hotkey", name)
+            }
+            Self::HotkeyNotRegistered(name) => {
+                write!(f, "'{}' has no registered hotkey", name)
+            }
             Self::Channel => write!(f, "hotkey event channel closed"),
-            Self::Interrupt(err) => write!(f, "input interrupted: {}", err),
+            Self::Config(err) => write!(f, "invalid hotkey config: {}", err),
         }
     }
 }
@@ -35,37 +35,120 @@ impl std::fmt::Display for HotkeyError {
 impl std::error::Error for HotkeyError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            #[cfg(target_os = "windows")]
             Self::Manager(err) | Self::Register(err) => Some(err),
-            Self::Interrupt(err) => Some(err),
+            Self::Config(err) => Some(err),
             _ => None,
         }
     }
 }
 
-#[cfg(target_os = "windows")]
-pub struct HotkeyListener {
-    rx: UnboundedReceiver<()>,
-    _manager: Arc<global_hotkey::GlobalHotKeyManager>,
-    _hotkey: global_hotkey::hotkey::HotKey,
+impl From<crate::keymap::ParseError> for HotkeyError {
+    fn from(err: crate::keymap::ParseError) -> Self {
+        Self::Config(err)
+    }
+}
+
+/// The mode that is always active, regardless of which mode the listener has
+/// switched to. Bindings registered without an explicit mode live here.
+pub const GLOBAL_MODE: &str = "global";
+
+/// What happens when a binding's hotkey fires.
+#[derive(Debug, Clone)]
+enum BindingAction {
+    /// Resolve `wait()` with this action name.
+    Action(String),
+    /// Switch the active mode to this mode instead of resolving `wait()`.
+    EnterMode(String),
+    /// Switch to the mode that isn't currently active, out of this pair,
+    /// instead of resolving `wait()` — lets one key toggle between two
+    /// modes (e.g. dictation <-> command) rather than only driving A->B.
+    ToggleMode(String, String),
+}
+
+/// A registered binding: which mode it is live in, what it does when fired,
+/// and whether it consumes the key (swallows it rather than passing it
+/// through to the focused application).
+#[derive(Debug, Clone)]
+struct Binding {
+    mode: String,
+    action: BindingAction,
+    consume: bool,
 }
 
-#[cfg(not(target_os = "windows"))]
+impl Binding {
+    fn fires_in(&self, current_mode: &str) -> bool {
+        self.mode == GLOBAL_MODE || self.mode == current_mode
+    }
+}
+
+/// Shared state consulted by the background event-forwarding thread: the
+/// active mode and every registered binding (of any mode), keyed by the
+/// `global_hotkey` id so the event loop can map `evt.id` straight back to a
+/// binding and then check whether it fires in the active mode.
+struct ListenerState {
+    current_mode: String,
+    bindings: HashMap<u32, Binding>,
+}
+
+/// Listens for registered global hotkeys and reports which action fired.
+///
+/// Backed by `global_hotkey`, which registers real OS-level hotkeys on
+/// Windows, Linux (X11/Wayland) and macOS, so push-to-talk and friends work
+/// in the background the same way on every platform.
+///
+/// Multiple named bindings can be registered at once, each scoped to a mode;
+/// `wait()` only resolves bindings that are live in the current mode, and
+/// firing a mode-switch or mode-toggle binding changes the active mode
+/// instead of returning an action. Bindings registered in [`GLOBAL_MODE`]
+/// are always live.
+///
+/// On macOS, `global_hotkey` requires its manager to be created on the
+/// process's main thread, so construct `HotkeyListener` there.
 pub struct HotkeyListener {
-    label: String,
+    rx: UnboundedReceiver<Fired>,
+    manager: Arc<global_hotkey::GlobalHotKeyManager>,
+    hotkeys: HashMap<String, global_hotkey::hotkey::HotKey>,
+    state: Arc<Mutex<ListenerState>>,
+}
+
+/// An action fired by [`HotkeyListener::wait`], and whether its binding is
+/// configured to consume the key rather than pass it through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fired {
+    pub action: String,
+    pub consume: bool,
 }
 
-#[cfg(target_os = "windows")]
-pub fn parse_hotkey(
-    hotkey: &str,
-) -> Result<
-    (
-        global_hotkey::hotkey::Modifiers,
-        global_hotkey::hotkey::Code,
-    ),
-    HotkeyError,
-> {
-    use global_hotkey::hotkey::{Code, Modifiers};
+/// A parsed hotkey spec, reversible back to its canonical string via
+/// `Display` (e.g. "Ctrl+Shift+F5") so a configured binding can be echoed
+/// back to the user or normalized on save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedHotkey {
+    pub modifiers: global_hotkey::hotkey::Modifiers,
+    pub code: global_hotkey::hotkey::Code,
+}
+
+impl std::fmt::Display for ParsedHotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use global_hotkey::hotkey::Modifiers;
+
+        for (flag, name) in [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SUPER, "Super"),
+            (Modifiers::META, "Meta"),
+        ] {
+            if self.modifiers.contains(flag) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", code_name(self.code))
+    }
+}
+
+pub fn parse_hotkey(hotkey: &str) -> Result<ParsedHotkey, HotkeyError> {
+    use global_hotkey::hotkey::Modifiers;
 
     let mut modifiers = Modifiers::empty();
     let mut code = None;
@@ -75,6 +158,8 @@ pub fn parse_hotkey(
             "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
             "alt" => modifiers |= Modifiers::ALT,
             "shift" => modifiers |= Modifiers::SHIFT,
+            "super" | "win" | "cmd" => modifiers |= Modifiers::SUPER,
+            "meta" => modifiers |= Modifiers::META,
             other => {
                 code =
                     Some(parse_code(other).ok_or_else(|| HotkeyError::Parse(other.to_string()))?);
@@ -82,10 +167,22 @@ pub fn parse_hotkey(
         }
     }
     let code = code.ok_or_else(|| HotkeyError::Parse("missing key".into()))?;
-    Ok((modifiers, code))
+    Ok(ParsedHotkey { modifiers, code })
+}
+
+/// Whether `token` is a recognized modifier name (see [`parse_hotkey`]).
+pub(crate) fn is_modifier(token: &str) -> bool {
+    matches!(
+        token,
+        "ctrl" | "control" | "alt" | "shift" | "super" | "win" | "cmd" | "meta"
+    )
+}
+
+/// Whether `token` is a recognized key name (see [`parse_code`]).
+pub(crate) fn is_keysym(token: &str) -> bool {
+    parse_code(token).is_some()
 }
 
-#[cfg(target_os = "windows")]
 fn parse_code(key: &str) -> Option<global_hotkey::hotkey::Code> {
     use global_hotkey::hotkey::Code;
     match key {
@@ -125,56 +222,291 @@ fn parse_code(key: &str) -> Option<global_hotkey::hotkey::Code> {
         "7" => Some(Code::Digit7),
         "8" => Some(Code::Digit8),
         "9" => Some(Code::Digit9),
+        "f1" => Some(Code::F1),
+        "f2" => Some(Code::F2),
+        "f3" => Some(Code::F3),
+        "f4" => Some(Code::F4),
+        "f5" => Some(Code::F5),
+        "f6" => Some(Code::F6),
+        "f7" => Some(Code::F7),
+        "f8" => Some(Code::F8),
+        "f9" => Some(Code::F9),
+        "f10" => Some(Code::F10),
+        "f11" => Some(Code::F11),
+        "f12" => Some(Code::F12),
+        "f13" => Some(Code::F13),
+        "f14" => Some(Code::F14),
+        "f15" => Some(Code::F15),
+        "f16" => Some(Code::F16),
+        "f17" => Some(Code::F17),
+        "f18" => Some(Code::F18),
+        "f19" => Some(Code::F19),
+        "f20" => Some(Code::F20),
+        "f21" => Some(Code::F21),
+        "f22" => Some(Code::F22),
+        "f23" => Some(Code::F23),
+        "f24" => Some(Code::F24),
+        "up" => Some(Code::ArrowUp),
+        "down" => Some(Code::ArrowDown),
+        "left" => Some(Code::ArrowLeft),
+        "right" => Some(Code::ArrowRight),
+        "space" => Some(Code::Space),
+        "enter" | "return" => Some(Code::Enter),
+        "tab" => Some(Code::Tab),
+        "escape" | "esc" => Some(Code::Escape),
+        "comma" | "," => Some(Code::Comma),
+        "period" | "." => Some(Code::Period),
+        "minus" | "-" => Some(Code::Minus),
+        "equal" | "=" => Some(Code::Equal),
+        "semicolon" | ";" => Some(Code::Semicolon),
+        "quote" | "'" => Some(Code::Quote),
+        "slash" | "/" => Some(Code::Slash),
+        "backslash" | "\\" => Some(Code::Backslash),
+        "bracketleft" | "[" => Some(Code::BracketLeft),
+        "bracketright" | "]" => Some(Code::BracketRight),
+        "backquote" | "grave" | "`" => Some(Code::Backquote),
         _ => None,
     }
 }
 
-#[cfg(target_os = "windows")]
+/// Inverse of [`parse_code`]: the canonical name for a code, used by
+/// `ParsedHotkey`'s `Display` impl.
+fn code_name(code: global_hotkey::hotkey::Code) -> &'static str {
+    use global_hotkey::hotkey::Code;
+    match code {
+        Code::KeyA => "A",
+        Code::KeyB => "B",
+        Code::KeyC => "C",
+        Code::KeyD => "D",
+        Code::KeyE => "E",
+        Code::KeyF => "F",
+        Code::KeyG => "G",
+        Code::KeyH => "H",
+        Code::KeyI => "I",
+        Code::KeyJ => "J",
+        Code::KeyK => "K",
+        Code::KeyL => "L",
+        Code::KeyM => "M",
+        Code::KeyN => "N",
+        Code::KeyO => "O",
+        Code::KeyP => "P",
+        Code::KeyQ => "Q",
+        Code::KeyR => "R",
+        Code::KeyS => "S",
+        Code::KeyT => "T",
+        Code::KeyU => "U",
+        Code::KeyV => "V",
+        Code::KeyW => "W",
+        Code::KeyX => "X",
+        Code::KeyY => "Y",
+        Code::KeyZ => "Z",
+        Code::Digit0 => "0",
+        Code::Digit1 => "1",
+        Code::Digit2 => "2",
+        Code::Digit3 => "3",
+        Code::Digit4 => "4",
+        Code::Digit5 => "5",
+        Code::Digit6 => "6",
+        Code::Digit7 => "7",
+        Code::Digit8 => "8",
+        Code::Digit9 => "9",
+        Code::F1 => "F1",
+        Code::F2 => "F2",
+        Code::F3 => "F3",
+        Code::F4 => "F4",
+        Code::F5 => "F5",
+        Code::F6 => "F6",
+        Code::F7 => "F7",
+        Code::F8 => "F8",
+        Code::F9 => "F9",
+        Code::F10 => "F10",
+        Code::F11 => "F11",
+        Code::F12 => "F12",
+        Code::F13 => "F13",
+        Code::F14 => "F14",
+        Code::F15 => "F15",
+        Code::F16 => "F16",
+        Code::F17 => "F17",
+        Code::F18 => "F18",
+        Code::F19 => "F19",
+        Code::F20 => "F20",
+        Code::F21 => "F21",
+        Code::F22 => "F22",
+        Code::F23 => "F23",
+        Code::F24 => "F24",
+        Code::ArrowUp => "Up",
+        Code::ArrowDown => "Down",
+        Code::ArrowLeft => "Left",
+        Code::ArrowRight => "Right",
+        Code::Space => "Space",
+        Code::Enter => "Enter",
+        Code::Tab => "Tab",
+        Code::Escape => "Escape",
+        Code::Comma => "Comma",
+        Code::Period => "Period",
+        Code::Minus => "Minus",
+        Code::Equal => "Equal",
+        Code::Semicolon => "Semicolon",
+        Code::Quote => "Quote",
+        Code::Slash => "Slash",
+        Code::Backslash => "Backslash",
+        Code::BracketLeft => "BracketLeft",
+        Code::BracketRight => "BracketRight",
+        Code::Backquote => "Backquote",
+        _ => "Unknown",
+    }
+}
+
 impl HotkeyListener {
-    pub fn new(cfg: &HotkeyConfig) -> Result<Self, HotkeyError> {
-        use global_hotkey::hotkey::HotKey;
+    pub fn new() -> Result<Self, HotkeyError> {
         use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager};
 
-        let (modifiers, code) = parse_hotkey(&cfg.key)?;
-        let hotkey = HotKey::new(Some(modifiers), code);
         let manager = Arc::new(GlobalHotKeyManager::new().map_err(HotkeyError::Manager)?);
-        manager.register(hotkey).map_err(HotkeyError::Register)?;
+        let state = Arc::new(Mutex::new(ListenerState {
+            current_mode: GLOBAL_MODE.to_string(),
+            bindings: HashMap::new(),
+        }));
         let (tx, rx) = mpsc::unbounded_channel();
         let global_event = GlobalHotKeyEvent::new();
-        let hotkey_id = hotkey.id();
+        let state_for_thread = Arc::clone(&state);
         std::thread::spawn(move || {
             while let Ok(evt) = global_event.receiver().recv() {
-                if evt.id == hotkey_id {
-                    let _ = tx.send(());
+                let mut state = state_for_thread.lock().unwrap();
+                let current_mode = state.current_mode.clone();
+                let Some(binding) = state.bindings.get(&evt.id) else {
+                    continue;
+                };
+                if !binding.fires_in(&current_mode) {
+                    continue;
+                }
+                let consume = binding.consume;
+                match binding.action.clone() {
+                    BindingAction::EnterMode(mode) => state.current_mode = mode,
+                    BindingAction::ToggleMode(mode_a, mode_b) => {
+                        state.current_mode = if current_mode == mode_a {
+                            mode_b
+                        } else {
+                            mode_a
+                        };
+                    }
+                    BindingAction::Action(action) => {
+                        let _ = tx.send(Fired { action, consume });
+                    }
                 }
             }
         });
         Ok(Self {
             rx,
-            _manager: manager,
-            _hotkey: hotkey,
+            manager,
+            hotkeys: HashMap::new(),
+            state,
         })
     }
 
-    pub async fn wait(&mut self) -> Result<(), HotkeyError> {
-        self.rx.recv().await.ok_or(HotkeyError::Channel)
+    /// Registers `hotkey_spec` in [`GLOBAL_MODE`] so that triggering it
+    /// resolves `wait()` with `name`.
+    pub fn register(&mut self, name: &str, hotkey_spec: &str) -> Result<(), HotkeyError> {
+        self.register_in_mode(name, hotkey_spec, GLOBAL_MODE, false)
     }
-}
 
-#[cfg(not(target_os = "windows"))]
-impl HotkeyListener {
-    pub fn new(cfg: &HotkeyConfig) -> Result<Self, HotkeyError> {
-        Ok(Self {
-            label: cfg.key.clone(),
-        })
+    /// Registers `hotkey_spec` so it only fires `wait()` with `name` while
+    /// `mode` is the active mode (bindings in [`GLOBAL_MODE`] always fire).
+    /// `consume` marks the binding as swallowing the key rather than passing
+    /// it through to the focused application; callers read it back off
+    /// `Fired::consume`.
+    pub fn register_in_mode(
+        &mut self,
+        name: &str,
+        hotkey_spec: &str,
+        mode: &str,
+        consume: bool,
+    ) -> Result<(), HotkeyError> {
+        self.insert(
+            name,
+            hotkey_spec,
+            Binding {
+                mode: mode.to_string(),
+                action: BindingAction::Action(name.to_string()),
+                consume,
+            },
+        )
     }
 
-    pub async fn wait(&mut self) -> Result<(), HotkeyError> {
-        println!("Press Enter to simulate hotkey '{}'", self.label);
-        let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .map_err(HotkeyError::Interrupt)?;
+    /// Registers `hotkey_spec` so that, while `mode` is active, triggering it
+    /// switches the active mode to `enter_mode` instead of resolving `wait()`.
+    pub fn register_mode_switch(
+        &mut self,
+        name: &str,
+        hotkey_spec: &str,
+        mode: &str,
+        enter_mode: &str,
+    ) -> Result<(), HotkeyError> {
+        self.insert(
+            name,
+            hotkey_spec,
+            Binding {
+                mode: mode.to_string(),
+                action: BindingAction::EnterMode(enter_mode.to_string()),
+                consume: false,
+            },
+        )
+    }
+
+    /// Registers `hotkey_spec` so that, while either `mode_a` or `mode_b` is
+    /// active, triggering it switches to the other one — a single physical
+    /// key toggling between two modes (e.g. dictation <-> command) instead
+    /// of needing one fixed-direction binding per mode. If neither mode is
+    /// active, it switches to `mode_a`.
+    pub fn register_mode_toggle(
+        &mut self,
+        name: &str,
+        hotkey_spec: &str,
+        mode_a: &str,
+        mode_b: &str,
+    ) -> Result<(), HotkeyError> {
+        self.insert(
+            name,
+            hotkey_spec,
+            Binding {
+                mode: GLOBAL_MODE.to_string(),
+                action: BindingAction::ToggleMode(mode_a.to_string(), mode_b.to_string()),
+                consume: false,
+            },
+        )
+    }
+
+    fn insert(&mut self, name: &str, hotkey_spec: &str, binding: Binding) -> Result<(), HotkeyError> {
+        use global_hotkey::hotkey::HotKey;
+
+        if self.hotkeys.contains_key(name) {
+            return Err(HotkeyError::HotkeyAlreadyRegistered(name.to_string()));
+        }
+        let parsed = parse_hotkey(hotkey_spec)?;
+        let hotkey = HotKey::new(Some(parsed.modifiers), parsed.code);
+        self.manager.register(hotkey).map_err(HotkeyError::Register)?;
+        self.state.lock().unwrap().bindings.insert(hotkey.id(), binding);
+        self.hotkeys.insert(name.to_string(), hotkey);
         Ok(())
     }
+
+    /// Removes a previously registered binding.
+    pub fn unregister(&mut self, name: &str) -> Result<(), HotkeyError> {
+        let hotkey = self
+            .hotkeys
+            .remove(name)
+            .ok_or_else(|| HotkeyError::HotkeyNotRegistered(name.to_string()))?;
+        self.manager
+            .unregister(hotkey)
+            .map_err(HotkeyError::Register)?;
+        self.state.lock().unwrap().bindings.remove(&hotkey.id());
+        Ok(())
+    }
+
+    /// Waits for a binding live in the current mode to fire and returns its
+    /// action name along with whether it consumes the key. Mode-switch and
+    /// mode-toggle bindings update the active mode internally and do not
+    /// resolve this call.
+    pub async fn wait(&mut self) -> Result<Fired, HotkeyError> {
+        self.rx.recv().await.ok_or(HotkeyError::Channel)
+    }
 }
@@ -0,0 +1,111 @@
+//! Text config format for hotkey-to-action bindings.
+//!
+//! Each non-blank, non-comment line binds a hotkey spec to an action:
+//!
+//! ```text
+//! # lines starting with '#' are comments
+//! ctrl+shift+r : start_recording
+//! ctrl+shift+p : toggle_pause consume
+//! ```
+//!
+//! The optional trailing `consume` flag marks a binding as swallowing the
+//! key (rather than passing it through to the focused application). Parse
+//! errors carry the 1-based line number of the offending binding so a
+//! malformed config points the user at the exact line.
+
+use crate::hotkey::{is_keysym, is_modifier, HotkeyError, HotkeyListener, GLOBAL_MODE};
+
+/// A single parsed binding: a hotkey spec, the action it fires, and whether
+/// it consumes the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub hotkey_spec: String,
+    pub action: String,
+    pub consume: bool,
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    UnknownSymbol(u32),
+    InvalidModifier(u32),
+    InvalidKeysym(u32),
+    MissingCommand(u32),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownSymbol(line) => write!(f, "line {}: unrecognized symbol", line),
+            Self::InvalidModifier(line) => write!(f, "line {}: invalid modifier", line),
+            Self::InvalidKeysym(line) => write!(f, "line {}: invalid key", line),
+            Self::MissingCommand(line) => write!(f, "line {}: missing action", line),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a keymap config's text into bindings. See the module docs for the
+/// line format.
+pub fn parse(source: &str) -> Result<Vec<Binding>, ParseError> {
+    let mut bindings = Vec::new();
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx as u32 + 1;
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (spec_part, rest) = line
+            .split_once(':')
+            .ok_or(ParseError::MissingCommand(line_no))?;
+
+        let mut rest_tokens = rest.split_whitespace();
+        let action = rest_tokens
+            .next()
+            .ok_or(ParseError::MissingCommand(line_no))?;
+        let consume = matches!(rest_tokens.next(), Some("consume"));
+
+        let hotkey_spec = parse_hotkey_spec(spec_part.trim(), line_no)?;
+        bindings.push(Binding {
+            hotkey_spec,
+            action: action.to_string(),
+            consume,
+        });
+    }
+    Ok(bindings)
+}
+
+fn parse_hotkey_spec(spec: &str, line_no: u32) -> Result<String, ParseError> {
+    let tokens: Vec<&str> = spec.split('+').map(|t| t.trim()).collect();
+    if tokens.iter().any(|t| t.is_empty()) {
+        return Err(ParseError::UnknownSymbol(line_no));
+    }
+    let (modifiers, key) = tokens.split_at(tokens.len() - 1);
+    for token in modifiers {
+        if !is_modifier(&token.to_lowercase()) {
+            return Err(ParseError::InvalidModifier(line_no));
+        }
+    }
+    if !is_keysym(&key[0].to_lowercase()) {
+        return Err(ParseError::InvalidKeysym(line_no));
+    }
+    Ok(spec.to_string())
+}
+
+/// Parses `source` and registers every binding with `listener`, so a
+/// user-editable keymap feeds the multi-hotkey manager directly. Each
+/// binding's `consume` flag carries through to `HotkeyListener`, so a
+/// caller reading `Fired::consume` off `wait()` sees what the config asked
+/// for.
+pub fn load(listener: &mut HotkeyListener, source: &str) -> Result<(), HotkeyError> {
+    for binding in parse(source)? {
+        listener.register_in_mode(
+            &binding.action,
+            &binding.hotkey_spec,
+            GLOBAL_MODE,
+            binding.consume,
+        )?;
+    }
+    Ok(())
+}
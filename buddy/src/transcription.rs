@@ -5,6 +5,8 @@ use vosk::{CompleteResult, Model, Recognizer};
 pub struct Transcriber {
     model: Arc<Model>,
     sample_rate: f32,
+    max_alternatives: u32,
+    enable_words: bool,
 }
 
 impl Transcriber {
@@ -16,19 +18,35 @@ impl Transcriber {
         Ok(Self {
             model: Arc::new(model),
             sample_rate: sample_rate as f32,
+            max_alternatives: cfg.max_alternatives,
+            enable_words: cfg.enable_word_timestamps,
         })
     }
 
     pub fn transcribe(&self, audio: &[i16]) -> Result<String, TranscriptionError> {
+        Ok(self.transcribe_detailed(audio)?.text)
+    }
+
+    /// Like [`transcribe`](Self::transcribe), but also returns Vosk's N-best
+    /// alternatives and word-level timestamps, when enabled via
+    /// `TranscriptionConfig::max_alternatives` / `enable_word_timestamps`, so
+    /// callers can pick alternatives or align words for highlighting/editing.
+    pub fn transcribe_detailed(
+        &self,
+        audio: &[i16],
+    ) -> Result<TranscriptResult, TranscriptionError> {
         if audio.is_empty() {
-            return Ok(String::new());
+            return Ok(TranscriptResult::default());
         }
 
         let mut recognizer = Recognizer::new(&self.model, self.sample_rate)
             .ok_or_else(|| TranscriptionError::Recognizer("failed to create recognizer".into()))?;
+        if self.max_alternatives > 0 {
+            recognizer.set_max_alternatives(self.max_alternatives);
+        }
+        recognizer.set_words(self.enable_words);
         recognizer.accept_waveform(audio);
-        let result = recognizer.final_result();
-        Ok(extract_text(result))
+        Ok(extract_detailed(recognizer.final_result()))
     }
 }
 
@@ -44,17 +62,75 @@ fn resolve_path(path: &Path) -> String {
     }
 }
 
-fn extract_text(result: CompleteResult<'_>) -> String {
+fn extract_detailed(result: CompleteResult<'_>) -> TranscriptResult {
     match result {
-        CompleteResult::Single(single) => single.text.to_string(),
-        CompleteResult::Multiple(multi) => multi
-            .alternatives
-            .first()
-            .map(|alt| alt.text.to_string())
-            .unwrap_or_default(),
+        CompleteResult::Single(single) => TranscriptResult {
+            text: single.text.to_string(),
+            alternatives: Vec::new(),
+            words: single.result.iter().map(to_word).collect(),
+        },
+        CompleteResult::Multiple(multi) => {
+            let alternatives: Vec<Alternative> = multi
+                .alternatives
+                .iter()
+                .map(|alt| Alternative {
+                    text: alt.text.to_string(),
+                    confidence: alt.confidence,
+                })
+                .collect();
+            let words = multi
+                .alternatives
+                .first()
+                .map(|alt| alt.result.iter().map(to_word).collect())
+                .unwrap_or_default();
+            let text = alternatives
+                .first()
+                .map(|alt| alt.text.clone())
+                .unwrap_or_default();
+            TranscriptResult {
+                text,
+                alternatives,
+                words,
+            }
+        }
+    }
+}
+
+fn to_word(word: &vosk::Word<'_>) -> Word {
+    Word {
+        text: word.word.to_string(),
+        start: word.start,
+        end: word.end,
+        confidence: word.conf,
     }
 }
 
+/// The outcome of [`Transcriber::transcribe_detailed`]: the best text, the
+/// full list of N-best alternatives it was drawn from, and word-level
+/// timestamps for the best result.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptResult {
+    pub text: String,
+    pub alternatives: Vec<Alternative>,
+    pub words: Vec<Word>,
+}
+
+/// One N-best candidate transcription and its confidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alternative {
+    pub text: String,
+    pub confidence: f32,
+}
+
+/// A single recognized word with its timing and confidence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
+}
+
 #[derive(Debug)]
 pub enum TranscriptionError {
     Model(String),